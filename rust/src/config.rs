@@ -0,0 +1,256 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Name of the environment variable that, when set, marks `CONFIG_PATH` as an
+/// AES-GCM-encrypted blob rather than plain TOML.
+const PASSPHRASE_ENV: &str = "SCHEDULEASE_CONFIG_PASSPHRASE";
+const CONFIG_PATH: &str = "config.toml";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const PBKDF2_ROUNDS: u32 = 600_000;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse config file: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Failed to decrypt config file: {msg}")]
+    Decrypt { msg: String },
+}
+
+pub struct Config {
+    pub user: UserConfig,
+    pub headers: HashMap<String, String>,
+    pub request_policy: RequestPolicy,
+    pub notifiers: NotifierConfig,
+}
+
+/// Which notification sinks `EamisService` should push terminal grab outcomes
+/// to. Each field is independently optional/toggleable so a user can enable
+/// any combination.
+#[derive(Clone, Debug, Default)]
+pub struct NotifierConfig {
+    pub webhook_url: Option<reqwest::Url>,
+    pub desktop_enabled: bool,
+}
+
+/// Timeout and retry tunables for the `reqwest` client, so a slow or flaky
+/// EAMIS endpoint during peak election time doesn't hang or fail hard on a
+/// single transient blip.
+#[derive(Clone, Debug)]
+pub struct RequestPolicy {
+    pub connect_timeout: std::time::Duration,
+    pub total_timeout: std::time::Duration,
+    pub max_retries: u32,
+    pub backoff_base: std::time::Duration,
+    pub backoff_multiplier: f64,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        RequestPolicy {
+            connect_timeout: std::time::Duration::from_secs(5),
+            total_timeout: std::time::Duration::from_secs(15),
+            max_retries: 3,
+            backoff_base: std::time::Duration::from_millis(300),
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+/// Account credentials. `encrypted_password` is wrapped in `SecretString` so
+/// it is zeroized on drop and never printed by `{:?}` or a logger.
+pub struct UserConfig {
+    pub account: String,
+    pub encrypted_password: SecretString,
+}
+
+impl std::fmt::Debug for UserConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserConfig")
+            .field("account", &self.account)
+            .field("encrypted_password", &self.encrypted_password)
+            .finish()
+    }
+}
+
+/// Shape of `config.toml` (or its decrypted contents) before the password is
+/// hashed and wrapped into `UserConfig`.
+#[derive(Deserialize)]
+struct RawConfig {
+    account: String,
+    password: SecretString,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    request_policy: RawRequestPolicy,
+    #[serde(default)]
+    notifications: RawNotifierConfig,
+}
+
+/// Optional `[notifications]` section.
+#[derive(Deserialize, Default)]
+struct RawNotifierConfig {
+    webhook_url: Option<String>,
+    #[serde(default)]
+    desktop_enabled: bool,
+}
+
+/// Optional `[request_policy]` section; any field left unset falls back to
+/// `RequestPolicy::default()`.
+#[derive(Deserialize, Default)]
+struct RawRequestPolicy {
+    connect_timeout_ms: Option<u64>,
+    total_timeout_ms: Option<u64>,
+    max_retries: Option<u32>,
+    backoff_base_ms: Option<u64>,
+    backoff_multiplier: Option<f64>,
+}
+
+impl From<RawRequestPolicy> for RequestPolicy {
+    fn from(raw: RawRequestPolicy) -> Self {
+        let default = RequestPolicy::default();
+        RequestPolicy {
+            connect_timeout: raw
+                .connect_timeout_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(default.connect_timeout),
+            total_timeout: raw
+                .total_timeout_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(default.total_timeout),
+            max_retries: raw.max_retries.unwrap_or(default.max_retries),
+            backoff_base: raw
+                .backoff_base_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(default.backoff_base),
+            backoff_multiplier: raw.backoff_multiplier.unwrap_or(default.backoff_multiplier),
+        }
+    }
+}
+
+/// Hash a plaintext password the way the EAMIS login API expects it.
+///
+/// This is the wire format the server requires, not an at-rest security
+/// boundary — a config file holding this hash is still as sensitive as the
+/// password itself. See `load_config`'s encrypted-file mode for that.
+pub fn encrypt(password: &str) -> String {
+    format!("{:x}", md5::compute(password))
+}
+
+/// Load `CONFIG_PATH`.
+///
+/// If the `SCHEDULEASE_CONFIG_PASSPHRASE` environment variable is set, the
+/// file is treated as an AES-GCM-encrypted blob (a random 16-byte salt, then
+/// a random 12-byte nonce, then the ciphertext, as written by
+/// `encrypt_config`; key derived from the passphrase and salt) and
+/// transparently decrypted before parsing; otherwise it is read as plain
+/// TOML.
+pub fn load_config() -> Result<Config, ConfigError> {
+    let bytes = std::fs::read(CONFIG_PATH)?;
+    let raw_toml = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => decrypt_config(&bytes, &passphrase)?,
+        Err(_) => String::from_utf8(bytes).map_err(|e| ConfigError::Decrypt {
+            msg: format!("Config file is not valid UTF-8: {}", e),
+        })?,
+    };
+
+    let raw: RawConfig = toml::from_str(&raw_toml)?;
+    Ok(Config {
+        user: UserConfig {
+            account: raw.account,
+            encrypted_password: SecretString::from(encrypt(raw.password.expose_secret())),
+        },
+        headers: raw.headers,
+        request_policy: raw.request_policy.into(),
+        notifiers: NotifierConfig {
+            webhook_url: raw
+                .notifications
+                .webhook_url
+                .as_deref()
+                .and_then(|url| reqwest::Url::parse(url).ok()),
+            desktop_enabled: raw.notifications.desktop_enabled,
+        },
+    })
+}
+
+/// Encrypt `plaintext_toml` with a key derived from `passphrase`, producing
+/// `salt || nonce || ciphertext` suitable for writing to `CONFIG_PATH`. The
+/// matching reader is `load_config`'s decryption path.
+pub fn encrypt_config(plaintext_toml: &str, passphrase: &str) -> Result<Vec<u8>, ConfigError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut ciphertext =
+        cipher
+            .encrypt(&nonce, plaintext_toml.as_bytes())
+            .map_err(|e| ConfigError::Decrypt {
+                msg: format!("Failed to encrypt config: {}", e),
+            })?;
+
+    let mut out = salt.to_vec();
+    out.extend_from_slice(&nonce);
+    out.append(&mut ciphertext);
+    Ok(out)
+}
+
+fn decrypt_config(bytes: &[u8], passphrase: &str) -> Result<String, ConfigError> {
+    if bytes.len() < SALT_LEN + NONCE_LEN {
+        return Err(ConfigError::Decrypt {
+            msg: "Encrypted config is too short to contain a salt and nonce.".to_string(),
+        });
+    }
+    let (salt, rest) = bytes.split_at(SALT_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt));
+    let plaintext = cipher.decrypt(nonce, ciphertext).map_err(|_| ConfigError::Decrypt {
+        msg: "Wrong passphrase or corrupted config file.".to_string(),
+    })?;
+
+    String::from_utf8(plaintext).map_err(|e| ConfigError::Decrypt {
+        msg: format!("Decrypted config is not valid UTF-8: {}", e),
+    })
+}
+
+/// Derive a 256-bit AES key from a user-supplied passphrase via PBKDF2-HMAC-SHA256,
+/// salted so the same passphrase doesn't produce the same key across configs and
+/// slow enough to make brute-forcing a weak passphrase impractical.
+fn derive_key(passphrase: &str, salt: &[u8]) -> aes_gcm::Key<Aes256Gcm> {
+    let mut key_bytes = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key_bytes);
+    *aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_config_round_trips_with_correct_passphrase() {
+        let toml = "account = \"u\"\npassword = \"p\"\n";
+        let encrypted = encrypt_config(toml, "hunter2").unwrap();
+        assert_eq!(decrypt_config(&encrypted, "hunter2").unwrap(), toml);
+    }
+
+    #[test]
+    fn decrypt_config_fails_with_wrong_passphrase() {
+        let encrypted = encrypt_config("account = \"u\"", "hunter2").unwrap();
+        assert!(decrypt_config(&encrypted, "wrong").is_err());
+    }
+
+    #[test]
+    fn decrypt_config_fails_on_truncated_input() {
+        assert!(decrypt_config(b"short", "hunter2").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Serialize;
+
+/// A terminal grab outcome worth surfacing to the user, since they are
+/// usually not watching the terminal by the time a multi-minute poll
+/// resolves.
+#[derive(Clone, Debug, Serialize)]
+pub struct NotificationEvent {
+    pub course_id: String,
+    pub profile_title: String,
+    pub message: String,
+    pub success: bool,
+}
+
+/// Something that can be told about a terminal grab outcome — success or a
+/// fatal (non-retryable) failure. Implement this for any push channel a user
+/// might want (webhook relay, desktop toast, ...).
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: &NotificationEvent);
+}
+
+/// POSTs the event as JSON to a configured webhook URL, e.g. a push relay.
+pub struct WebhookNotifier {
+    url: reqwest::Url,
+    client: reqwest::blocking::Client,
+}
+
+impl WebhookNotifier {
+    /// `connect_timeout`/`total_timeout` should come from the same
+    /// `RequestPolicy` as the main EAMIS client: `notify` runs synchronously
+    /// on the grab loop's thread, so an unbounded wait on an unresponsive
+    /// webhook would hang the whole grab loop.
+    pub fn new(
+        url: reqwest::Url,
+        connect_timeout: std::time::Duration,
+        total_timeout: std::time::Duration,
+    ) -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .connect_timeout(connect_timeout)
+            .timeout(total_timeout)
+            .build()
+            .expect("Failed to build webhook client");
+        WebhookNotifier { url, client }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, event: &NotificationEvent) {
+        if let Err(e) = self.client.post(self.url.clone()).json(event).send() {
+            eprintln!("Failed to deliver webhook notification: {}", e);
+        }
+    }
+}
+
+/// Shows a local desktop toast.
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, event: &NotificationEvent) {
+        let summary = if event.success { "选课成功" } else { "选课失败" };
+        let body = format!("{}: {}", event.profile_title, event.message);
+        if let Err(e) = notify_rust::Notification::new()
+            .summary(summary)
+            .body(&body)
+            .show()
+        {
+            eprintln!("Failed to show desktop notification: {}", e);
+        }
+    }
+}
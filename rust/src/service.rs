@@ -1,10 +1,12 @@
 use crate::config::Config;
+use futures::future;
 use once_cell::sync::Lazy;
 use reqwest::{
     blocking::{Client, RequestBuilder},
     header::{HeaderMap, HeaderName, HeaderValue},
     Url,
 };
+use secrecy::{ExposeSecret, SecretString};
 use std::str::FromStr; // Required for HeaderName::from_str
 
 static LOGIN_URL: Lazy<Url> =
@@ -36,7 +38,7 @@ pub enum ServiceError {
     ParseError { msg: String },
 
     #[error("Course election failed: {msg}")]
-    ElectionError { msg: String },
+    ElectionError { msg: String, retryable: bool },
 }
 
 pub struct EamisService {
@@ -44,10 +46,31 @@ pub struct EamisService {
     headers: HeaderMap,
 
     account: String,
-    encrypted_password: String,
+    encrypted_password: SecretString,
 
     postlogin_url: Option<Url>,
     profiles: Option<Vec<Profile>>,
+
+    /// `server_time - local_time`, measured once during `initial_connection`
+    /// from the `Date` response header. Zero until then.
+    server_offset: chrono::Duration,
+
+    cookie_store: std::sync::Arc<reqwest_cookie_store::CookieStoreMutex>,
+    session_path: std::path::PathBuf,
+
+    request_policy: crate::config::RequestPolicy,
+
+    notifiers: Vec<Box<dyn crate::notify::Notifier>>,
+}
+
+/// What gets written to `session_path` by `save_session` and read back by
+/// `new()`, so a restart can reuse a still-valid login instead of paying for
+/// a fresh IAM login and CSRF round-trip.
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct PersistedSession {
+    postlogin_url: Option<String>,
+    #[serde(default)]
+    cookies: String,
 }
 
 #[derive(Clone, Debug)]
@@ -57,6 +80,119 @@ pub struct Profile {
     pub id: String,
 }
 
+/// A single time slot within a course's weekly schedule, as rendered by EAMIS
+/// (e.g. "1-16周 周一 1-2节").
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct TimeSlot {
+    #[serde(default)]
+    pub weeks: String,
+    #[serde(default)]
+    pub day: String,
+    #[serde(default)]
+    pub periods: String,
+}
+
+/// A course as returned by `stdElectCourse!data.action`.
+#[derive(Clone, Debug)]
+pub struct Course {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    pub teacher: String,
+    pub capacity: u32,
+    pub elected: u32,
+    pub schedule: Vec<TimeSlot>,
+}
+
+impl Course {
+    /// Remaining seats, saturating at zero if the course is over-enrolled.
+    pub fn available(&self) -> u32 {
+        self.capacity.saturating_sub(self.elected)
+    }
+}
+
+/// Raw shape of a single entry in the `lessonJSONs` array. Field names mirror
+/// the server's own (inconsistent) casing, so we deserialize into this first
+/// and convert into the public `Course` type.
+#[derive(Debug, serde::Deserialize)]
+struct RawLesson {
+    id: String,
+    #[serde(default)]
+    no: String,
+    name: String,
+    #[serde(default)]
+    teachers: String,
+    #[serde(default, rename = "limitCount")]
+    limit_count: u32,
+    #[serde(default, rename = "stdCount")]
+    std_count: u32,
+    #[serde(default)]
+    scheduled: Vec<TimeSlot>,
+}
+
+impl From<RawLesson> for Course {
+    fn from(raw: RawLesson) -> Self {
+        Course {
+            id: raw.id,
+            code: raw.no,
+            name: raw.name,
+            teacher: raw.teachers,
+            capacity: raw.limit_count,
+            elected: raw.std_count,
+            schedule: raw.scheduled,
+        }
+    }
+}
+
+/// Chainable filter over a locally cached course list.
+#[derive(Default)]
+pub struct CourseQuery {
+    name_contains: Option<String>,
+    teacher: Option<String>,
+    only_available: bool,
+}
+
+impl CourseQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only courses whose name contains `needle`.
+    pub fn name_contains(mut self, needle: impl Into<String>) -> Self {
+        self.name_contains = Some(needle.into());
+        self
+    }
+
+    /// Keep only courses taught by someone matching `teacher`.
+    pub fn teacher(mut self, teacher: impl Into<String>) -> Self {
+        self.teacher = Some(teacher.into());
+        self
+    }
+
+    /// Keep only courses that still have a free seat.
+    pub fn only_available(mut self) -> Self {
+        self.only_available = true;
+        self
+    }
+
+    /// Apply the query to a course list, returning the matches in order.
+    pub fn apply<'a>(&self, courses: &'a [Course]) -> Vec<&'a Course> {
+        courses
+            .iter()
+            .filter(|course| {
+                self.name_contains
+                    .as_ref()
+                    .is_none_or(|needle| course.name.contains(needle.as_str()))
+                    && self
+                        .teacher
+                        .as_ref()
+                        .is_none_or(|teacher| course.teacher.contains(teacher.as_str()))
+                    && (!self.only_available || course.available() > 0)
+            })
+            .collect()
+    }
+}
+
 #[derive(PartialEq)]
 pub enum Operation {
     Elect,
@@ -70,16 +206,35 @@ impl EamisService {
         let headers = HeaderMap::new();
 
         for (key, value) in &config.headers {
-            let header_name =
-                HeaderName::from_str(key).expect(&format!("Invalid header name: {}", key));
+            let header_name = HeaderName::from_str(key)
+                .unwrap_or_else(|_| panic!("Invalid header name: {}", key));
             let header_value = HeaderValue::from_str(value)
-                .expect(&format!("Invalid header value for {}: {}", key, value));
+                .unwrap_or_else(|_| panic!("Invalid header value for {}: {}", key, value));
             default_headers.insert(header_name, header_value);
         }
+        let session_path = std::path::PathBuf::from("session.json");
+        let (cookie_store, postlogin_url) = Self::load_session(&session_path);
+        let cookie_store = std::sync::Arc::new(cookie_store);
+
+        let mut notifiers: Vec<Box<dyn crate::notify::Notifier>> = Vec::new();
+        if let Some(url) = &config.notifiers.webhook_url {
+            notifiers.push(Box::new(crate::notify::WebhookNotifier::new(
+                url.clone(),
+                config.request_policy.connect_timeout,
+                config.request_policy.total_timeout,
+            )));
+        }
+        if config.notifiers.desktop_enabled {
+            notifiers.push(Box::new(crate::notify::DesktopNotifier));
+        }
+
+        let request_policy = config.request_policy.clone();
         let client = Client::builder()
-            .cookie_store(true)
+            .cookie_provider(cookie_store.clone())
             .redirect(reqwest::redirect::Policy::limited(10))
             .default_headers(default_headers) // Use the newly created HeaderMap
+            .connect_timeout(request_policy.connect_timeout)
+            .timeout(request_policy.total_timeout)
             .build()
             .expect("Failed to build reqwest client");
 
@@ -88,11 +243,95 @@ impl EamisService {
             headers,
             account: config.user.account.clone(),
             encrypted_password: config.user.encrypted_password.clone(),
-            postlogin_url: None,
+            postlogin_url,
             profiles: None,
+            server_offset: chrono::Duration::zero(),
+            cookie_store,
+            session_path,
+            request_policy,
+            notifiers,
+        }
+    }
+
+    /// Push a terminal grab outcome to every configured notifier. `profile_id`
+    /// is resolved to a human-readable title via the cached `profiles` list
+    /// when available, falling back to the raw id.
+    fn notify_terminal(&self, course_id: &str, profile_id: &str, message: &str, success: bool) {
+        if self.notifiers.is_empty() {
+            return;
+        }
+
+        let profile_title = self
+            .profiles
+            .as_ref()
+            .and_then(|profiles| profiles.iter().find(|p| p.id == profile_id))
+            .map(|p| p.title.clone())
+            .unwrap_or_else(|| profile_id.to_string());
+
+        let event = crate::notify::NotificationEvent {
+            course_id: course_id.to_string(),
+            profile_title,
+            message: message.to_string(),
+            success,
+        };
+
+        for notifier in &self.notifiers {
+            notifier.notify(&event);
         }
     }
 
+    /// Load a previously persisted session from `path`, if one exists.
+    /// Falls back to an empty cookie jar and no cached `postlogin_url`.
+    fn load_session(
+        path: &std::path::Path,
+    ) -> (reqwest_cookie_store::CookieStoreMutex, Option<Url>) {
+        let persisted: PersistedSession = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        let cookie_store =
+            cookie_store::CookieStore::load_json(persisted.cookies.as_bytes()).unwrap_or_default();
+        let postlogin_url = persisted.postlogin_url.and_then(|raw| Url::parse(&raw).ok());
+
+        (
+            reqwest_cookie_store::CookieStoreMutex::new(cookie_store),
+            postlogin_url,
+        )
+    }
+
+    /// Persist the cookie jar and the resolved `postlogin_url` to
+    /// `session_path`, so the next `new()` can skip a fresh IAM login.
+    /// Called automatically whenever a (re-)login resolves `postlogin_url`.
+    pub fn save_session(&self) -> Result<(), ServiceError> {
+        let mut cookies = Vec::new();
+        self.cookie_store
+            .lock()
+            .unwrap()
+            .save_json(&mut cookies)
+            .map_err(|e| ServiceError::ParseError {
+                msg: format!("Failed to serialize cookie store: {}", e),
+            })?;
+
+        let persisted = PersistedSession {
+            postlogin_url: self.postlogin_url.as_ref().map(|url| url.to_string()),
+            cookies: String::from_utf8(cookies).map_err(|e| ServiceError::ParseError {
+                msg: format!("Cookie store produced invalid UTF-8: {}", e),
+            })?,
+        };
+
+        let file = std::fs::File::create(&self.session_path).map_err(|e| ServiceError::ParseError {
+            msg: format!(
+                "Failed to open session file {}: {}",
+                self.session_path.display(),
+                e
+            ),
+        })?;
+        serde_json::to_writer(file, &persisted).map_err(|e| ServiceError::ParseError {
+            msg: format!("Failed to write session file: {}", e),
+        })
+    }
+
     // ---- Helper Functions ----
     /// A helper method to append headers to get request.
     pub fn get(&self, url: &Url) -> RequestBuilder {
@@ -103,6 +342,90 @@ impl EamisService {
     pub fn post(&self, url: &Url) -> RequestBuilder {
         self.client.post(url.clone()).headers(self.headers.clone())
     }
+    /// Send a request, transparently recovering from an expired session.
+    ///
+    /// If the response was bounced back to the IAM home page — the same
+    /// signal `get_profiles` already checked for manually — the cached
+    /// `postlogin_url` is dropped, `login()` is re-run, and the request is
+    /// retried once against the refreshed session.
+    fn send_with_relogin(
+        &mut self,
+        builder: RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, ServiceError> {
+        let retry_builder = builder.try_clone();
+        let response = self.send_with_backoff(builder)?;
+
+        if Self::bounced_to_home(response.url()) {
+            if let Some(retry_builder) = retry_builder {
+                self.postlogin_url = None;
+                let fresh_url = self.login()?;
+                self.postlogin_url = Some(fresh_url);
+                if let Err(e) = self.save_session() {
+                    eprintln!("Failed to persist session after re-login: {}", e);
+                }
+                return self.send_with_backoff(retry_builder);
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Send a request, retrying connection errors and 5xx responses with
+    /// exponential backoff plus jitter, per `self.request_policy`. 4xx
+    /// responses are returned as-is (not retried) so the caller's own
+    /// parsing can fail fast, since repeating an invalid request wastes an
+    /// election-time retry budget for nothing.
+    fn send_with_backoff(
+        &self,
+        mut builder: RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, ServiceError> {
+        let mut attempt = 0;
+        loop {
+            let retry_builder = builder.try_clone();
+            match builder.send() {
+                Ok(response) if response.status().is_server_error() => {
+                    match retry_builder {
+                        Some(next) if attempt < self.request_policy.max_retries => {
+                            self.sleep_backoff(attempt);
+                            attempt += 1;
+                            builder = next;
+                        }
+                        _ => return Ok(response),
+                    }
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if (e.is_connect() || e.is_timeout()) && attempt < self.request_policy.max_retries => {
+                    match retry_builder {
+                        Some(next) => {
+                            self.sleep_backoff(attempt);
+                            attempt += 1;
+                            builder = next;
+                        }
+                        None => return Err(ServiceError::ConnectionError(e)),
+                    }
+                }
+                Err(e) => return Err(ServiceError::ConnectionError(e)),
+            }
+        }
+    }
+
+    /// Sleep for `backoff_base * backoff_multiplier ^ attempt`, plus a little
+    /// jitter so concurrent requests don't all retry in lockstep.
+    fn sleep_backoff(&self, attempt: u32) {
+        let backoff = self
+            .request_policy
+            .backoff_base
+            .mul_f64(self.request_policy.backoff_multiplier.powi(attempt as i32));
+        let jitter = std::time::Duration::from_millis(rand::random::<u64>() % 100);
+        std::thread::sleep(backoff + jitter);
+    }
+
+    /// Whether `url` indicates we were redirected to the IAM login/home page
+    /// instead of the page we actually requested.
+    fn bounced_to_home(url: &Url) -> bool {
+        url.host_str().is_some_and(|host| host == "iam.nankai.edu.cn") || url.path().contains("home")
+    }
+
     /// Helper function to create a timestamp in milliseconds.
     pub fn create_timestamp() -> String {
         let now = std::time::SystemTime::now();
@@ -112,9 +435,22 @@ impl EamisService {
     /// Test the initial connection to the EAMIS service. Raises `ConnectionError` if the connection fails.
     ///
     /// This is a single method that must be invoked manually to ensure the service is reachable.
+    ///
+    /// As a side effect, this reads the `Date` response header to compute the
+    /// drift between the server's clock and ours, so that `schedule_election`
+    /// can trigger relative to server time rather than local time.
     pub fn initial_connection(&mut self) -> Result<(), ServiceError> {
-        let response = self.get(&EAMIS_URL).send()?;
-        response.error_for_status()?;
+        let response = self.send_with_backoff(self.get(&EAMIS_URL))?;
+        response.error_for_status_ref()?;
+
+        if let Some(server_time) = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|raw| chrono::DateTime::parse_from_rfc2822(raw).ok())
+        {
+            self.server_offset = server_time.with_timezone(&chrono::Utc) - chrono::Utc::now();
+        }
 
         self.headers.insert(
             HeaderName::from_static("sec-fetch-site"),
@@ -123,6 +459,48 @@ impl EamisService {
         Ok(())
     }
 
+    /// The current time, adjusted by the drift measured in `initial_connection`.
+    /// Falls back to the local clock (zero offset) if that method was never called.
+    pub fn server_now(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now() + self.server_offset
+    }
+
+    /// Idle until `target` (in server time) and then immediately attempt to
+    /// elect `course_id`.
+    ///
+    /// Far from the deadline this sleeps in coarse, OS-scheduled chunks to
+    /// avoid burning CPU; inside the last second it busy-waits instead, since
+    /// thread-sleep granularity can easily overshoot by tens of milliseconds
+    /// right when that latency matters most.
+    pub fn schedule_election(
+        &mut self,
+        target: chrono::DateTime<chrono::Utc>,
+        course_id: &str,
+        profile_id: &str,
+    ) -> Result<(), ServiceError> {
+        let spin_threshold = chrono::Duration::seconds(1);
+
+        loop {
+            let remaining = target - self.server_now();
+            if remaining <= chrono::Duration::zero() {
+                break;
+            }
+            if remaining <= spin_threshold {
+                while self.server_now() < target {
+                    std::hint::spin_loop();
+                }
+                break;
+            }
+            // Sleep most of the remaining time, minus the last second which
+            // we spin-wait for precision, leaving headroom for sleep overshoot.
+            if let Ok(coarse_sleep) = (remaining - spin_threshold).to_std() {
+                std::thread::sleep(coarse_sleep);
+            }
+        }
+
+        self.elect_course(course_id, profile_id, Operation::Elect)
+    }
+
     // ---- Cached Properties ----
 
     /// Returns the post-login URL.
@@ -135,18 +513,18 @@ impl EamisService {
         // If postlogin_url is not set, we need to login first
         let login_url = self.login().unwrap();
         self.postlogin_url = Some(login_url.clone());
+        if let Err(e) = self.save_session() {
+            eprintln!("Failed to persist session after login: {}", e);
+        }
         login_url
     }
     /// Login to the EAMIS service
     pub fn login(&mut self) -> Result<Url, ServiceError> {
         // Redirect to site
         let prelogin_response = self
-            .get(&SITE_URL)
-            .send()
-            .unwrap()
+            .send_with_backoff(self.get(&SITE_URL))?
             .error_for_status()
-            .map_err(ServiceError::ConnectionError)
-            .unwrap();
+            .map_err(ServiceError::ConnectionError)?;
         // API call to login
         let csrf_token = prelogin_response
             .cookies()
@@ -185,15 +563,16 @@ impl EamisService {
         ]);
 
         let login_response = self
-            .post(&LOGIN_API)
-            .json(&serde_json::json!({
-                "login_scene": "feilian",
-                "account_type": "userid",
-                "account": self.account,
-                "password": self.encrypted_password,
-            }))
-            .headers(login_headers)
-            .send()?
+            .send_with_backoff(
+                self.post(&LOGIN_API)
+                    .json(&serde_json::json!({
+                        "login_scene": "feilian",
+                        "account_type": "userid",
+                        "account": self.account,
+                        "password": self.encrypted_password.expose_secret(),
+                    }))
+                    .headers(login_headers),
+            )?
             .error_for_status()?;
 
         let content: serde_json::Value =
@@ -238,10 +617,7 @@ impl EamisService {
                     e
                 ),
             })?;
-        let postlogin_response = self
-            .get(&link)
-            .send()
-            .map_err(ServiceError::ConnectionError)?;
+        let postlogin_response = self.send_with_backoff(self.get(&link))?;
         println!("Login successful. Redirecting to: {}", link);
         Ok(postlogin_response.url().clone())
     }
@@ -259,28 +635,30 @@ impl EamisService {
     pub fn get_profiles(&mut self) -> Result<Vec<Profile>, ServiceError> {
         let postlogin_url = self.postlogin_url();
 
-        let course_elect_menu_response = self
-            .get(&PROFILE_URL)
-            .header("Referer", postlogin_url.as_str())
-            .header("X-Requested-With", "XMLHttpRequest")
-            .query(&[("_", EamisService::create_timestamp())])
-            .send()?;
+        let course_elect_menu_response = self.send_with_relogin(
+            self.get(&PROFILE_URL)
+                .header("Referer", postlogin_url.as_str())
+                .header("X-Requested-With", "XMLHttpRequest")
+                .query(&[("_", EamisService::create_timestamp())]),
+        )?;
 
         let response_url = course_elect_menu_response.url().clone();
         let content = course_elect_menu_response.text()?;
         let document = scraper::Html::parse_document(&content);
 
         // Check if we got redirected to the wrong page
-        if response_url.path().contains("home") {
+        if Self::bounced_to_home(&response_url) {
             return Err(ServiceError::ElectionError {
                 msg: "Request was redirected to home page instead of course selection page."
                     .to_string(),
+                retryable: false,
             });
         }
 
         if content.contains("无法选课") || content.contains("未到选课时间") {
             return Err(ServiceError::ElectionError {
                 msg: "Course election menu is currently not available..".to_string(),
+                retryable: false,
             });
         }
 
@@ -297,7 +675,7 @@ impl EamisService {
                     .next()
                 {
                     if let Some(href) = link_element.value().attr("href") {
-                        let profile_id = href.split('=').last().unwrap_or("");
+                        let profile_id = href.split('=').next_back().unwrap_or("");
                         if let Ok(url) = EAMIS_URL.join(href) {
                             course_categories.push(Profile {
                                 title,
@@ -313,6 +691,46 @@ impl EamisService {
         Ok(course_categories)
     }
 
+    /// Fetch the course list for a given election `profile`.
+    ///
+    /// The endpoint does not return plain JSON: it returns a `<script>`-style
+    /// assignment, e.g. `var lessonJSONs = [...];`. We strip everything
+    /// outside the outermost `[...]` before handing the rest to `serde_json`.
+    pub fn get_courses(&mut self, profile: &Profile) -> Result<Vec<Course>, ServiceError> {
+        let postlogin_url = self.postlogin_url();
+
+        let response = self.send_with_relogin(
+            self.get(&COURSE_INFO_URL)
+                .header("Referer", postlogin_url.as_str())
+                .header("X-Requested-With", "XMLHttpRequest")
+                .query(&[
+                    ("profileId", profile.id.as_str()),
+                    ("_", &EamisService::create_timestamp()),
+                ]),
+        )?;
+
+        let body = response.text()?;
+        let json = Self::extract_lesson_array(&body)?;
+        let raw_lessons: Vec<RawLesson> =
+            serde_json::from_str(json).map_err(|e| ServiceError::ParseError {
+                msg: format!("Failed to parse course list: {}", e),
+            })?;
+
+        Ok(raw_lessons.into_iter().map(Course::from).collect())
+    }
+
+    /// Strip the `var lessonJSONs = ` prefix and trailing `;` from the raw
+    /// response body, leaving just the JSON array literal.
+    fn extract_lesson_array(body: &str) -> Result<&str, ServiceError> {
+        let start = body.find('[').ok_or_else(|| ServiceError::ParseError {
+            msg: "Course list response did not contain an array literal.".to_string(),
+        })?;
+        let end = body.rfind(']').ok_or_else(|| ServiceError::ParseError {
+            msg: "Course list response did not contain an array literal.".to_string(),
+        })?;
+        Ok(&body[start..=end])
+    }
+
     pub fn elect_course(
         &mut self,
         course_id: &str,
@@ -323,31 +741,42 @@ impl EamisService {
             Operation::Elect => "elect",
             Operation::Cancel => "cancel",
         };
+        let postlogin_url = self.postlogin_url();
+
+        let elect_response = self.send_with_relogin(
+            self.post(&ELECT_URL)
+                .header("Referer", postlogin_url.as_str())
+                .header("X-Requested-With", "XMLHttpRequest")
+                .query(&[("_", EamisService::create_timestamp())])
+                .form(&[
+                    ("optype", opt),
+                    ("operator0", &format!("{}:{}:0", course_id, opt)),
+                    ("lesson0", course_id),
+                    ("profileId", profile_id),
+                ]),
+        );
 
-        let elect_response = self
-            .post(&ELECT_URL)
-            .header("Referer", self.postlogin_url().as_str())
-            .header("X-Requested-With", "XMLHttpRequest")
-            .query(&[("_", EamisService::create_timestamp())])
-            .form(&[
-                ("optype", opt),
-                ("operator0", &format!("{}:{}:0", course_id, opt)),
-                ("lesson0", course_id),
-                ("profileId", profile_id),
-            ])
-            .send()?;
-
-        let content = elect_response.text()?;
+        // A request or read failure is not retried by `grab_course` either,
+        // so it is notified like any other terminal failure.
+        let content = match elect_response.and_then(|response| response.text().map_err(ServiceError::from)) {
+            Ok(content) => content,
+            Err(e) => {
+                self.notify_terminal(course_id, profile_id, &e.to_string(), false);
+                return Err(e);
+            }
+        };
         println!("Elect response: {}", content);
-        if content.contains("选课成功") {
+        let result = if content.contains("选课成功") {
             Ok(())
         } else if content.contains("当前选课不开放") {
             Err(ServiceError::ElectionError {
                 msg: "Course election is currently not open.".to_string(),
+                retryable: true,
             })
         } else if content.contains("已经选过") {
             Err(ServiceError::ElectionError {
                 msg: format!("Course {} is already elected.", course_id),
+                retryable: false,
             })
         } else if content.contains("计划外名额已满") {
             Err(ServiceError::ElectionError {
@@ -355,6 +784,7 @@ impl EamisService {
                     "Course {} is considered as extra and has no available spots.",
                     course_id
                 ),
+                retryable: true,
             })
         } else if content.contains("退课成功") && operation == Operation::Cancel {
             Ok(())
@@ -364,7 +794,256 @@ impl EamisService {
                     "Failed to elect or cancel course {}. Response: {}",
                     course_id, content
                 ),
+                retryable: false,
+            })
+        };
+
+        // Only notify on an outcome that won't be retried by a caller like
+        // `grab_course` — a transient "not open"/"full" response isn't news.
+        match &result {
+            Ok(()) => self.notify_terminal(course_id, profile_id, "选课成功", true),
+            Err(ServiceError::ElectionError {
+                retryable: true, ..
+            }) => {}
+            Err(e) => self.notify_terminal(course_id, profile_id, &e.to_string(), false),
+        }
+
+        result
+    }
+
+    /// Repeatedly attempt to elect a full course until a seat opens, the
+    /// policy's deadline passes, or the returned [`GrabHandle`] is cancelled.
+    ///
+    /// The polling loop runs on its own dedicated tokio runtime (this crate
+    /// otherwise uses the blocking `reqwest` client), so the call returns
+    /// immediately with a handle rather than blocking the caller. `on_progress`
+    /// is invoked after every attempt, successful or not, with the attempt
+    /// count and the server's last message.
+    pub fn grab_course(
+        service: std::sync::Arc<std::sync::Mutex<EamisService>>,
+        course_id: String,
+        profile_id: String,
+        policy: GrabPolicy,
+        mut on_progress: impl FnMut(GrabProgress) + Send + 'static,
+    ) -> GrabHandle {
+        let (abort_handle, abort_registration) = future::AbortHandle::new_pair();
+        let shared_handle = std::sync::Arc::new(std::sync::Mutex::new(Some(abort_handle)));
+        let grab_handle = GrabHandle {
+            abort_handle: shared_handle.clone(),
+        };
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_time()
+                .build()
+                .expect("Failed to start grab runtime");
+
+            let polling = Self::poll_until_resolved(service, course_id, profile_id, policy, move |progress| {
+                on_progress(progress)
+            });
+
+            // Errors out only when cancelled; the poll loop reports its own
+            // terminal success/failure through `on_progress`.
+            let _ = runtime.block_on(future::Abortable::new(polling, abort_registration));
+        });
+
+        grab_handle
+    }
+
+    async fn poll_until_resolved(
+        service: std::sync::Arc<std::sync::Mutex<EamisService>>,
+        course_id: String,
+        profile_id: String,
+        policy: GrabPolicy,
+        mut on_progress: impl FnMut(GrabProgress),
+    ) {
+        let started = std::time::Instant::now();
+        let mut attempt: u32 = 0;
+
+        loop {
+            if let Some(deadline) = policy.deadline {
+                if started.elapsed() >= deadline {
+                    let message = "Deadline elapsed before a seat opened up.".to_string();
+                    service
+                        .lock()
+                        .unwrap()
+                        .notify_terminal(&course_id, &profile_id, &message, false);
+                    on_progress(GrabProgress { attempt, message });
+                    return;
+                }
+            }
+
+            attempt += 1;
+
+            let (course_id_task, profile_id_task, service_task) =
+                (course_id.clone(), profile_id.clone(), service.clone());
+            let result = tokio::task::spawn_blocking(move || {
+                service_task
+                    .lock()
+                    .unwrap()
+                    .elect_course(&course_id_task, &profile_id_task, Operation::Elect)
             })
+            .await
+            .expect("Grab worker thread panicked");
+
+            match result {
+                Ok(()) => {
+                    on_progress(GrabProgress {
+                        attempt,
+                        message: "选课成功".to_string(),
+                    });
+                    return;
+                }
+                Err(ServiceError::ElectionError {
+                    msg,
+                    retryable: true,
+                }) => {
+                    on_progress(GrabProgress {
+                        attempt,
+                        message: msg,
+                    });
+                }
+                Err(e) => {
+                    on_progress(GrabProgress {
+                        attempt,
+                        message: e.to_string(),
+                    });
+                    return;
+                }
+            }
+
+            let jitter_ms = policy.jitter.as_millis().max(1) as u64;
+            let jitter = std::time::Duration::from_millis(rand::random::<u64>() % jitter_ms);
+            tokio::time::sleep(policy.base_interval + jitter).await;
+        }
+    }
+}
+
+/// A progress snapshot emitted by [`EamisService::grab_course`] after each
+/// polling attempt.
+#[derive(Clone, Debug)]
+pub struct GrabProgress {
+    pub attempt: u32,
+    pub message: String,
+}
+
+/// Tunables for a [`EamisService::grab_course`] polling loop.
+#[derive(Clone, Debug)]
+pub struct GrabPolicy {
+    pub base_interval: std::time::Duration,
+    pub jitter: std::time::Duration,
+    pub deadline: Option<std::time::Duration>,
+}
+
+impl Default for GrabPolicy {
+    fn default() -> Self {
+        GrabPolicy {
+            base_interval: std::time::Duration::from_secs(3),
+            jitter: std::time::Duration::from_millis(500),
+            deadline: None,
+        }
+    }
+}
+
+/// A handle to a running [`EamisService::grab_course`] task.
+#[derive(Clone)]
+pub struct GrabHandle {
+    abort_handle: std::sync::Arc<std::sync::Mutex<Option<future::AbortHandle>>>,
+}
+
+impl GrabHandle {
+    /// Stop the associated polling loop at its next await point. Safe to call
+    /// more than once; later calls are a no-op.
+    pub fn cancel(&self) {
+        if let Some(handle) = self.abort_handle.lock().unwrap().take() {
+            handle.abort();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_lesson_array_strips_assignment_and_semicolon() {
+        let body = "var lessonJSONs = [{\"id\":\"1\"}];";
+        assert_eq!(
+            EamisService::extract_lesson_array(body).unwrap(),
+            "[{\"id\":\"1\"}]"
+        );
+    }
+
+    #[test]
+    fn extract_lesson_array_handles_empty_array() {
+        let body = "var lessonJSONs = [];";
+        assert_eq!(EamisService::extract_lesson_array(body).unwrap(), "[]");
+    }
+
+    #[test]
+    fn extract_lesson_array_errors_without_brackets() {
+        let body = "无法选课";
+        assert!(EamisService::extract_lesson_array(body).is_err());
+    }
+
+    fn course(name: &str, teacher: &str, capacity: u32, elected: u32) -> Course {
+        Course {
+            id: name.to_string(),
+            code: name.to_string(),
+            name: name.to_string(),
+            teacher: teacher.to_string(),
+            capacity,
+            elected,
+            schedule: Vec::new(),
+        }
+    }
+
+    fn course_names<'a>(courses: &[&'a Course]) -> Vec<&'a str> {
+        courses.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn course_query_with_no_filters_keeps_everything() {
+        let courses = vec![course("Calculus", "Zhang", 10, 10), course("Physics", "Li", 5, 0)];
+        let matches = CourseQuery::new().apply(&courses);
+        assert_eq!(course_names(&matches), vec!["Calculus", "Physics"]);
+    }
+
+    #[test]
+    fn course_query_filters_by_name_contains() {
+        let courses = vec![course("Calculus I", "Zhang", 10, 0), course("Physics", "Li", 10, 0)];
+        let matches = CourseQuery::new().name_contains("Calc").apply(&courses);
+        assert_eq!(course_names(&matches), vec!["Calculus I"]);
+    }
+
+    #[test]
+    fn course_query_filters_by_teacher() {
+        let courses = vec![course("Calculus", "Zhang San", 10, 0), course("Physics", "Li Si", 10, 0)];
+        let matches = CourseQuery::new().teacher("Li").apply(&courses);
+        assert_eq!(course_names(&matches), vec!["Physics"]);
+    }
+
+    #[test]
+    fn course_query_only_available_excludes_full_courses() {
+        // capacity == elected means 0 seats left, so it should be excluded.
+        let courses = vec![course("Full", "Zhang", 10, 10), course("Open", "Li", 10, 9)];
+        let matches = CourseQuery::new().only_available().apply(&courses);
+        assert_eq!(course_names(&matches), vec!["Open"]);
+    }
+
+    #[test]
+    fn course_query_combines_filters_with_and_semantics() {
+        let courses = vec![
+            course("Calculus I", "Zhang", 10, 10), // name matches, teacher matches, but full
+            course("Calculus I", "Li", 10, 0),      // name matches, teacher doesn't
+            course("Physics", "Zhang", 10, 0),      // teacher matches, name doesn't
+            course("Calculus II", "Zhang", 10, 0),  // matches every filter
+        ];
+        let matches = CourseQuery::new()
+            .name_contains("Calculus")
+            .teacher("Zhang")
+            .only_available()
+            .apply(&courses);
+        assert_eq!(course_names(&matches), vec!["Calculus II"]);
+    }
+}